@@ -0,0 +1,57 @@
+use std::{io, time::Duration};
+
+use color_eyre::Result;
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use tui::{backend::CrosstermBackend, Terminal};
+
+use super::Key;
+
+pub type Impl = CrosstermBackend<io::Stdout>;
+
+pub fn init() -> Result<Terminal<Impl>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+pub fn restore(terminal: &mut Terminal<Impl>) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+/// Best-effort teardown for the panic hook, where we no longer have access
+/// to the `Terminal` that owns the raw stdout handle.
+pub fn emergency_restore() -> Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    Terminal::new(CrosstermBackend::new(io::stdout()))?.show_cursor()?;
+    Ok(())
+}
+
+pub fn next_event(timeout: Duration) -> Result<Option<Key>> {
+    if !event::poll(timeout)? {
+        return Ok(None);
+    }
+    let key = match event::read()? {
+        Event::Key(key) => match key.code {
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Esc,
+            _ => Key::Other,
+        },
+        _ => Key::Other,
+    };
+    Ok(Some(key))
+}
@@ -0,0 +1,39 @@
+//! Terminal backend shims, selected at compile time via the `crossterm`
+//! and `termion` Cargo features (default: `crossterm`). `run_app`/`ui` only
+//! ever see the backend-agnostic [`Key`] enum and the `init`/`restore`/
+//! `emergency_restore`/`next_event` functions re-exported here, so the app
+//! logic stays backend-generic.
+//!
+//! The two features are mutually exclusive: since `default = ["crossterm"]`
+//! is additive, switching to termion means building with
+//! `--no-default-features --features termion` rather than just
+//! `--features termion`.
+
+#[cfg(all(feature = "crossterm", feature = "termion"))]
+compile_error!(
+    "features `crossterm` and `termion` are mutually exclusive; build with \
+     `--no-default-features --features termion` to select termion"
+);
+
+#[cfg(not(any(feature = "crossterm", feature = "termion")))]
+compile_error!("enable exactly one of the `crossterm` or `termion` features");
+
+#[cfg(all(feature = "crossterm", not(feature = "termion")))]
+mod crossterm_backend;
+#[cfg(all(feature = "crossterm", not(feature = "termion")))]
+pub use crossterm_backend::{emergency_restore, init, next_event, restore};
+
+#[cfg(all(feature = "termion", not(feature = "crossterm")))]
+mod termion_backend;
+#[cfg(all(feature = "termion", not(feature = "crossterm")))]
+pub use termion_backend::{emergency_restore, init, next_event, restore};
+
+/// Backend-agnostic subset of the keys `run_app` reacts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    Enter,
+    Esc,
+    Other,
+}
@@ -0,0 +1,94 @@
+use std::{
+    io::{self, Write},
+    sync::{mpsc, Mutex, OnceLock},
+    thread,
+    time::Duration,
+};
+
+use color_eyre::Result;
+use termion::{
+    event::Key as TermionKey,
+    input::{MouseTerminal, TermRead},
+    raw::{IntoRawMode, RawTerminal},
+    screen::{AlternateScreen, ToMainScreen},
+};
+use tui::{backend::TermionBackend, Terminal};
+
+use super::Key;
+
+/// Mirrors the private `EXIT_MOUSE_SEQUENCE` that `MouseTerminal`'s `Drop`
+/// writes, so `emergency_restore` can disable mouse capture without one.
+const EXIT_MOUSE_SEQUENCE: &str = "\x1B[?1006l\x1B[?1015l\x1B[?1002l\x1B[?1000l";
+
+pub type Impl = TermionBackend<AlternateScreen<MouseTerminal<io::Stdout>>>;
+
+/// Termion has no `poll`-with-timeout like crossterm, so a background
+/// thread blocks on `stdin().keys()` and forwards them over this channel,
+/// letting `next_event` emulate the same timeout-based polling.
+static KEYS: OnceLock<Mutex<mpsc::Receiver<TermionKey>>> = OnceLock::new();
+
+/// Raw mode is a tty-wide setting (not owned by any particular `Stdout`
+/// handle), so this guard is kept separate from the `AlternateScreen`/
+/// `MouseTerminal` stack used for drawing. Stashing it here lets both
+/// `restore` and `emergency_restore` switch back to cooked mode on demand,
+/// instead of only on drop — which a panic hook runs before.
+static RAW_MODE: OnceLock<Mutex<RawTerminal<io::Stdout>>> = OnceLock::new();
+
+pub fn init() -> Result<Terminal<Impl>> {
+    let raw_mode = io::stdout().into_raw_mode()?;
+    let _ = RAW_MODE.set(Mutex::new(raw_mode));
+
+    let stdout = MouseTerminal::from(io::stdout());
+    let stdout = AlternateScreen::from(stdout);
+    let terminal = Terminal::new(TermionBackend::new(stdout))?;
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for key in io::stdin().keys().flatten() {
+            if tx.send(key).is_err() {
+                break;
+            }
+        }
+    });
+    let _ = KEYS.set(Mutex::new(rx));
+
+    Ok(terminal)
+}
+
+pub fn restore(terminal: &mut Terminal<Impl>) -> Result<()> {
+    if let Some(raw_mode) = RAW_MODE.get() {
+        raw_mode.lock().unwrap().suspend_raw_mode()?;
+    }
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+/// Leaves the alternate screen, disables mouse capture and suspends raw
+/// mode directly, without relying on `AlternateScreen`/`MouseTerminal`'s
+/// `Drop` impls — those only run once unwinding reaches them, which is
+/// after `std::panic::set_hook`'s closure (and the panic report it prints)
+/// has already run.
+pub fn emergency_restore() -> Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{}{}", ToMainScreen, EXIT_MOUSE_SEQUENCE)?;
+    stdout.flush()?;
+    if let Some(raw_mode) = RAW_MODE.get() {
+        raw_mode.lock().unwrap().suspend_raw_mode()?;
+    }
+    Ok(())
+}
+
+pub fn next_event(timeout: Duration) -> Result<Option<Key>> {
+    let keys = KEYS.get().expect("backend::init must run before next_event");
+    match keys.lock().unwrap().recv_timeout(timeout) {
+        Ok(key) => Ok(Some(match key {
+            TermionKey::Char('\n') => Key::Enter,
+            TermionKey::Char(c) => Key::Char(c),
+            TermionKey::Backspace => Key::Backspace,
+            TermionKey::Esc => Key::Esc,
+            _ => Key::Other,
+        })),
+        Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Ok(None),
+    }
+}
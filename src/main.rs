@@ -1,24 +1,29 @@
-use std::{io, time::Duration};
+use std::time::{Duration, Instant};
 
 use color_eyre::Result;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
 use tui::{
-    backend::{Backend, CrosstermBackend},
+    backend::Backend,
     layout::*,
     style::{Color, Modifier, Style},
     symbols,
     text::Span,
-    widgets::{Axis, Block, Borders, Chart, Dataset, List, ListItem, Paragraph},
+    widgets::{
+        Axis, BarChart, Block, Borders, Chart, Dataset, List, ListItem, Paragraph, Sparkline,
+    },
     Frame, Terminal,
 };
 
+mod backend;
 mod meassurement;
+use backend::Key;
 use meassurement::Meassurement;
 
+const LOW_BOUND: i16 = 80;
+const IN_RANGE_BOUND: i16 = 180;
+const HIGH_BOUND: i16 = 300;
+/// Number of trailing meassurements fed into the least-squares trend line.
+const TREND_WINDOW: usize = 10;
+
 enum InputMode {
     Level,
     Time,
@@ -61,16 +66,48 @@ impl App {
         self.time_input.clear();
         Ok(())
     }
+
+    /// Ordinary least-squares fit of `y = intercept + slope * t` over the last
+    /// `TREND_WINDOW` meassurements. Returns `None` when there aren't enough
+    /// distinct timestamps to fit a line.
+    fn trend(&self) -> Option<(f64, f64)> {
+        let recent = self
+            .meassurements
+            .iter()
+            .rev()
+            .take(TREND_WINDOW)
+            .map(|m| (m.timestamp() as f64, m.y() as f64))
+            .collect::<Vec<_>>();
+        if recent.len() < 2 {
+            return None;
+        }
+        let n = recent.len() as f64;
+        let t_mean = recent.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let y_mean = recent.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let denom = recent.iter().map(|(t, _)| (t - t_mean).powi(2)).sum::<f64>();
+        if denom == 0.0 {
+            return None;
+        }
+        let numer = recent
+            .iter()
+            .map(|(t, y)| (t - t_mean) * (y - y_mean))
+            .sum::<f64>();
+        let slope = numer / denom;
+        let intercept = y_mean - slope * t_mean;
+        Some((slope, intercept))
+    }
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
 
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let original_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let _ = backend::emergency_restore();
+        original_hook(panic_info);
+    }));
+
+    let mut terminal = backend::init()?;
 
     terminal.draw(|f| {
         let size = f.size();
@@ -79,47 +116,53 @@ fn main() -> Result<()> {
     })?;
 
     let app = App::new();
-    let res = run_app(&mut terminal, app);
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-
-    terminal.show_cursor()?;
+    let tick_rate = Duration::from_secs(1);
+    let res = run_app(&mut terminal, app, tick_rate);
+    backend::restore(&mut terminal)?;
+
     res
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<()> {
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    tick_rate: Duration,
+) -> Result<()> {
+    let mut last_tick = Instant::now();
     loop {
         terminal.draw(|f| ui(f, &app))?;
 
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Esc => return Ok(()),
-                KeyCode::Backspace => {
+        let timeout = tick_rate
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0));
+        if let Some(key) = backend::next_event(timeout)? {
+            match key {
+                Key::Esc => return Ok(()),
+                Key::Backspace => {
                     match app.input_mode {
                         InputMode::Level => app.level_input.pop(),
                         InputMode::Time => app.time_input.pop(),
                     };
                 }
-                KeyCode::Char(c) => {
+                Key::Char(c) => {
                     match app.input_mode {
                         InputMode::Level => app.level_input.push(c),
                         InputMode::Time => app.time_input.push(c),
                     };
                 }
-                KeyCode::Enter => match app.input_mode {
+                Key::Enter => match app.input_mode {
                     InputMode::Level => app.input_mode = InputMode::Time,
                     InputMode::Time => {
                         app.add_meassure()?;
                         app.input_mode = InputMode::Level;
                     }
                 },
-                _ => continue,
+                Key::Other => continue,
             }
         }
+        if last_tick.elapsed() >= tick_rate {
+            last_tick = Instant::now();
+        }
     }
 }
 
@@ -132,6 +175,7 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(8),
+                Constraint::Length(3),
                 Constraint::Min(1),
                 Constraint::Length(3),
             ]
@@ -157,7 +201,7 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         let m = &app.meassurements[i];
         items.push(ListItem::new(format!(
             "{} {}",
-            m.to_string(),
+            m,
             if i > 0 {
                 format!("({:+.3} / min)", m.diff(&app.meassurements[i - 1]))
             } else {
@@ -166,15 +210,74 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         )));
     }
 
+    let list_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+        .split(chunks[2]);
+
     f.render_widget(
         List::new(items).block(
             Block::default()
                 .borders(Borders::TOP)
                 .title("Meassurements"),
         ),
-        chunks[2],
+        list_chunks[0],
     );
 
+    let zone_counts = {
+        let mut counts = [0u64; 4];
+        for m in &app.meassurements {
+            let y = m.y();
+            let zone = if y < LOW_BOUND {
+                0
+            } else if y < IN_RANGE_BOUND {
+                1
+            } else if y < HIGH_BOUND {
+                2
+            } else {
+                3
+            };
+            counts[zone] += 1;
+        }
+        counts
+    };
+    let zone_data = [
+        ("Low", zone_counts[0]),
+        ("In-range", zone_counts[1]),
+        ("High", zone_counts[2]),
+        ("V.High", zone_counts[3]),
+    ];
+    let bar_chart = BarChart::default()
+        .block(Block::default().borders(Borders::ALL).title("Time in range"))
+        .bar_width(7)
+        .bar_gap(2)
+        .value_style(Style::default().fg(Color::Black).bg(Color::Green))
+        .label_style(Style::default().fg(Color::White))
+        .data(&zone_data);
+    f.render_widget(bar_chart, list_chunks[1]);
+
+    let recent_levels = app
+        .meassurements
+        .iter()
+        .rev()
+        .take(chunks[3].width as usize)
+        .rev()
+        .map(|m| m.y().max(0) as u64)
+        .collect::<Vec<_>>();
+    let rising = app.meassurements.len() >= 2
+        && app
+            .meassurements
+            .last()
+            .zip(app.meassurements.get(app.meassurements.len() - 2))
+            .map(|(latest, prev)| latest.diff(prev) >= 0.0)
+            .unwrap_or(false);
+    let sparkline = Sparkline::default()
+        .block(Block::default().borders(Borders::ALL).title("Recent"))
+        .data(&recent_levels)
+        .max(HIGH_BOUND as u64)
+        .style(Style::default().fg(if rising { Color::Red } else { Color::Green }));
+    f.render_widget(sparkline, chunks[3]);
+
     let data = app
         .meassurements
         .iter()
@@ -185,7 +288,30 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         .graph_type(tui::widgets::GraphType::Line)
         .style(Style::default().fg(Color::Cyan))
         .data(&data);
-    let chart = Chart::new(vec![dataset])
+
+    let trend = app.trend();
+    let trend_data = trend.and_then(|(slope, intercept)| {
+        let t_min = data.iter().map(|(t, _)| *t).reduce(f64::min)?;
+        let t_max = data.iter().map(|(t, _)| *t).reduce(f64::max)?;
+        Some(vec![
+            (t_min, intercept + slope * t_min),
+            (t_max, intercept + slope * t_max),
+        ])
+    });
+    let trend_dataset = trend_data.as_ref().map(|trend_data| {
+        Dataset::default()
+            .name("Trend")
+            .marker(symbols::Marker::Dot)
+            .graph_type(tui::widgets::GraphType::Line)
+            .style(Style::default().fg(Color::Magenta))
+            .data(trend_data)
+    });
+
+    let mut datasets = vec![dataset];
+    if let Some(trend_dataset) = trend_dataset {
+        datasets.push(trend_dataset);
+    }
+    let chart = Chart::new(datasets)
         .block(
             Block::default()
                 .title(Span::styled(
@@ -201,25 +327,24 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
                 data.iter()
                     .map(|dp| dp.0)
                     .reduce(f64::min)
-                    .unwrap_or_else(|| 0.0)
+                    .unwrap_or(0.0)
                     * 0.9,
                 1440.0,
             ]),
         )
         .y_axis(Axis::default().title("Level").bounds([0.0, 400.0]));
-    f.render_widget(chart, chunks[3]);
+    f.render_widget(chart, chunks[4]);
 
     let time_to_text = {
         let mut text = String::from("Waiting for meassurements...");
-        if app.meassurements.len() >= 2 {
-            let mf = app.meassurements.last();
-            let mpf = app.meassurements.get(app.meassurements.len() - 2);
-            if let Some((level, rate)) = mf.and_then(|mf| Some((mf.y(), mf.diff(mpf.unwrap())))) {
-                let (variant, time) = if rate <= 0.0 {
-                    ("low", (80 - level) as f32 / rate)
+        if let Some((slope, _)) = trend {
+            if let Some(level) = app.meassurements.last().map(|m| m.y() as f64) {
+                let (variant, target) = if slope <= 0.0 {
+                    ("low", LOW_BOUND)
                 } else {
-                    ("high", (300 - level) as f32 / rate)
+                    ("high", HIGH_BOUND)
                 };
+                let time = (target as f64 - level) / slope;
                 text = format!(
                     "Time to {}: {}",
                     variant,
@@ -230,7 +355,7 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         text
     };
     let time_to = Paragraph::new(time_to_text).block(Block::default().borders(Borders::ALL));
-    f.render_widget(time_to, chunks[4]);
+    f.render_widget(time_to, chunks[5]);
 
     let (idx, len) = match app.input_mode {
         InputMode::Level => (0, app.level_input.len() as u16),
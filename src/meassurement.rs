@@ -57,8 +57,8 @@ impl Meassurement {
     }
 }
 
-impl ToString for Meassurement {
-    fn to_string(&self) -> String {
-        format!("[{}] {}", self.t, self.y)
+impl Display for Meassurement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.t, self.y)
     }
 }